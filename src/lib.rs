@@ -46,93 +46,640 @@
 //! assert_eq!(either.times(1, |x| x + 2), 3);
 //! ```
 //!
+//! # Supertraits and default methods
+//!
+//! A supertrait bound (`trait Apply: Clone`) is also required of `L` and
+//! `R`, so `Either<L, R>` satisfies it. A method can be annotated with
+//! `#[either(skip)]` to leave it to the trait's own default implementation
+//! instead of force-delegating it; such a method must provide a default
+//! body.
+//!
+//! # Arbitrary enums
+//!
+//! `either_trait` only targets [`Either`](https://crates.io/crates/either),
+//! which has exactly two variants.
+//! For a user-defined sum type with any number of single-field tuple
+//! variants, use `either_enum` instead, restating the enum's shape as the
+//! macro's argument (a proc-macro attribute cannot see items elsewhere in
+//! the crate):
+//!
+//! ```rust,ignore
+//! #[either_enum(enum Either3<A, B, C> { First(A), Second(B), Third(C) })]
+//! trait Apply {
+//!     fn times<T, F>(&self, t: T, f: F) -> T
+//!     where
+//!         F: Fn(T) -> T;
+//! }
+//! ```
+//!
 //! # Limitations
 //!
-//! This macro only supports traits without any associated
-//! constant or associated type.
+//! This macro only supports traits without any associated constant.
 //! Generic type parameters of the trait must not be `L` or `R`.
 //! The first parameter of a trait method must be `self`,
 //! `&self` or `&mut self`.
-//! The types of other parameters and the return type
-//! must not contain `Self`.
+//! The types of other parameters must not contain `Self`; an associated
+//! type projection such as `Self::Item` is allowed.
+//! The return type may be `Self` (or `Self` wrapped in `Result` or
+//! `Option`), in which case the result is re-wrapped into `Either`;
+//! any other occurrence of `Self` in the return type is not supported.
 
 extern crate proc_macro;
 
 use proc_macro::TokenStream;
-use quote::quote;
+use quote::{format_ident, quote};
 use syn::{
-    parse_macro_input, parse_quote, FnArg, Generics, Ident, ItemTrait, TraitItem, TraitItemMethod,
+    parse_macro_input, parse_quote, punctuated::Punctuated, spanned::Spanned, Attribute, Error,
+    Fields, FnArg, GenericArgument, GenericParam, Generics, Ident, ItemEnum, ItemTrait,
+    PathArguments, ReturnType, Signature, Token, TraitItem, TraitItemMethod, TraitItemType, Type,
+    TypeParamBound,
 };
 
-fn either_method(method: &TraitItemMethod) -> proc_macro2::TokenStream {
-    let sig = &method.sig;
-    let name = &sig.ident;
-    if let FnArg::Receiver(_) = sig.inputs[0] {
-        let args_left = sig.inputs.iter().skip(1).map(|arg| {
-            if let FnArg::Typed(arg) = arg {
-                &arg.pat
+/// Folds a `Vec<syn::Error>` collected while walking the trait into a single
+/// error, so that every violation is reported in one pass instead of
+/// aborting on the first.
+fn combine_errors(errors: Vec<Error>) -> Option<Error> {
+    errors.into_iter().reduce(|mut combined, error| {
+        combined.combine(error);
+        combined
+    })
+}
+
+/// Whether `attr` is the helper attribute `#[either(skip)]`, which leaves a
+/// method to the trait's own provided (default) implementation instead of
+/// force-delegating it.
+fn is_skip_attr(attr: &Attribute) -> bool {
+    attr.path.is_ident("either")
+        && attr
+            .parse_args::<Ident>()
+            .is_ok_and(|ident| ident == "skip")
+}
+
+/// Whether a method's return type is `Self`, possibly wrapped in `Result`
+/// or `Option`, and therefore needs its result re-wrapped into `Either`.
+#[derive(Clone, Copy)]
+enum SelfWrap {
+    None,
+    Direct,
+    Result,
+    Option,
+}
+
+fn is_self_type(ty: &Type) -> bool {
+    matches!(ty, Type::Path(path) if path.qself.is_none() && path.path.is_ident("Self"))
+}
+
+/// Recurses into a type to find `Self`, including through references,
+/// pointers, slices, arrays, tuples and generic arguments (e.g. `&Self`,
+/// `[Self]`, `(i32, Self)` or `Vec<Self>`), since all of these are just as
+/// undispatchable as a bare `Self` parameter. An associated type projection
+/// such as `Self::Item` is not counted, and function pointer and trait
+/// object types are not searched.
+fn type_contains_self(ty: &Type) -> bool {
+    if is_self_type(ty) {
+        return true;
+    }
+    match ty {
+        Type::Path(path) => path.path.segments.iter().any(|segment| {
+            if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                args.args.iter().any(|arg| match arg {
+                    GenericArgument::Type(ty) => type_contains_self(ty),
+                    _ => false,
+                })
             } else {
-                unreachable!()
+                false
             }
-        });
-        let args_right = args_left.clone();
-        quote! {
-            #sig {
-                match self {
-                    either::Either::Left(left) => left.#name(#(#args_left),*),
-                    either::Either::Right(right) => right.#name(#(#args_right),*),
+        }),
+        Type::Reference(reference) => type_contains_self(&reference.elem),
+        Type::Ptr(ptr) => type_contains_self(&ptr.elem),
+        Type::Slice(slice) => type_contains_self(&slice.elem),
+        Type::Array(array) => type_contains_self(&array.elem),
+        Type::Paren(paren) => type_contains_self(&paren.elem),
+        Type::Group(group) => type_contains_self(&group.elem),
+        Type::Tuple(tuple) => tuple.elems.iter().any(type_contains_self),
+        _ => false,
+    }
+}
+
+fn self_wrap(ty: &Type) -> SelfWrap {
+    if is_self_type(ty) {
+        return SelfWrap::Direct;
+    }
+    if let Type::Path(path) = ty {
+        if let Some(segment) = path.path.segments.last() {
+            if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                if let Some(GenericArgument::Type(inner)) = args.args.first() {
+                    if is_self_type(inner) {
+                        if segment.ident == "Result" {
+                            return SelfWrap::Result;
+                        }
+                        if segment.ident == "Option" {
+                            return SelfWrap::Option;
+                        }
+                    }
                 }
             }
         }
+    }
+    SelfWrap::None
+}
+
+/// Rewrites a signature's `Self`/`Result<Self, _>`/`Option<Self>` return type
+/// into the same shape over `Either<L, R>`.
+fn rewrite_self_output(sig: &Signature, wrap: &SelfWrap) -> Signature {
+    let mut sig = sig.clone();
+    if let ReturnType::Type(_, ty) = &mut sig.output {
+        match wrap {
+            SelfWrap::None => {}
+            SelfWrap::Direct => *ty = parse_quote!(either::Either<L, R>),
+            SelfWrap::Result => {
+                if let Type::Path(path) = ty.as_mut() {
+                    let segment = path.path.segments.last_mut().unwrap();
+                    if let PathArguments::AngleBracketed(args) = &mut segment.arguments {
+                        if let Some(GenericArgument::Type(inner)) = args.args.first_mut() {
+                            *inner = parse_quote!(either::Either<L, R>);
+                        }
+                    }
+                }
+            }
+            SelfWrap::Option => {
+                if let Type::Path(path) = ty.as_mut() {
+                    let segment = path.path.segments.last_mut().unwrap();
+                    if let PathArguments::AngleBracketed(args) = &mut segment.arguments {
+                        if let Some(GenericArgument::Type(inner)) = args.args.first_mut() {
+                            *inner = parse_quote!(either::Either<L, R>);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    sig
+}
+
+/// Checks a method's receiver and parameters, and returns its rewritten
+/// signature, its [`SelfWrap`] and the patterns used to forward its
+/// arguments. Pushes a `syn::Error` for each violation found (a non-`self`
+/// receiver, or `Self` in a parameter type) and returns `None`.
+fn validate_method<'a>(
+    method: &'a TraitItemMethod,
+    errors: &mut Vec<Error>,
+) -> Option<(
+    Signature,
+    SelfWrap,
+    impl Iterator<Item = &'a syn::Pat> + Clone,
+)> {
+    let sig = &method.sig;
+    let receiver_ok = match sig.inputs.first() {
+        Some(FnArg::Receiver(_)) => true,
+        Some(arg) => {
+            errors.push(Error::new(
+                arg.span(),
+                "the first parameter of a trait method must be `self`, `&self` or `&mut self`",
+            ));
+            false
+        }
+        None => {
+            errors.push(Error::new(
+                sig.span(),
+                "the first parameter of a trait method must be `self`, `&self` or `&mut self`",
+            ));
+            false
+        }
+    };
+    if !receiver_ok {
+        return None;
+    }
+
+    let mut ok = true;
+    for arg in sig.inputs.iter().skip(1) {
+        if let FnArg::Typed(arg) = arg {
+            if type_contains_self(&arg.ty) {
+                errors.push(Error::new(
+                    arg.ty.span(),
+                    "`Self` in a parameter type cannot be dispatched, since the arms of the enum might hold different variants",
+                ));
+                ok = false;
+            }
+        }
+    }
+    if !ok {
+        return None;
+    }
+
+    let args = sig.inputs.iter().skip(1).map(|arg| {
+        if let FnArg::Typed(arg) = arg {
+            &*arg.pat
+        } else {
+            unreachable!()
+        }
+    });
+
+    let wrap = match &sig.output {
+        ReturnType::Type(_, ty) => self_wrap(ty),
+        ReturnType::Default => SelfWrap::None,
+    };
+    let sig = rewrite_self_output(sig, &wrap);
+
+    Some((sig, wrap, args))
+}
+
+/// Builds the dispatching method body for the two-armed `Either`, or `None`
+/// if [`validate_method`] reported a violation.
+fn either_method(
+    method: &TraitItemMethod,
+    errors: &mut Vec<Error>,
+) -> Option<proc_macro2::TokenStream> {
+    let name = &method.sig.ident;
+    let (sig, wrap, args) = validate_method(method, errors)?;
+    let args_right = args.clone();
+
+    let (left_call, right_call) = match wrap {
+        SelfWrap::None => (
+            quote!(left.#name(#(#args),*)),
+            quote!(right.#name(#(#args_right),*)),
+        ),
+        SelfWrap::Direct => (
+            quote!(either::Either::Left(left.#name(#(#args),*))),
+            quote!(either::Either::Right(right.#name(#(#args_right),*))),
+        ),
+        SelfWrap::Result | SelfWrap::Option => (
+            quote!(left.#name(#(#args),*).map(either::Either::Left)),
+            quote!(right.#name(#(#args_right),*).map(either::Either::Right)),
+        ),
+    };
+
+    Some(quote! {
+        #sig {
+            match self {
+                either::Either::Left(left) => #left_call,
+                either::Either::Right(right) => #right_call,
+            }
+        }
+    })
+}
+
+/// Builds the dispatching method body for an arbitrary enum of single-field
+/// tuple variants, matching each `#enum_name::#variant(binding)` and
+/// forwarding the call on `binding`, or `None` if [`validate_method`]
+/// reported a violation.
+fn either_enum_method(
+    method: &TraitItemMethod,
+    enum_name: &Ident,
+    variants: &[Ident],
+    errors: &mut Vec<Error>,
+) -> Option<proc_macro2::TokenStream> {
+    let name = &method.sig.ident;
+    // The signature is kept as declared: `Self` already names the enum in its
+    // impl, whereas the rewritten one would name `either::Either<L, R>`.
+    let (_, wrap, args) = validate_method(method, errors)?;
+    let sig = &method.sig;
+
+    let arms = variants.iter().enumerate().map(|(i, variant)| {
+        let binding = format_ident!("value{}", i);
+        let args = args.clone();
+        let call = match wrap {
+            SelfWrap::None => quote!(#binding.#name(#(#args),*)),
+            SelfWrap::Direct => quote!(#enum_name::#variant(#binding.#name(#(#args),*))),
+            SelfWrap::Result | SelfWrap::Option => {
+                quote!(#binding.#name(#(#args),*).map(#enum_name::#variant))
+            }
+        };
+        quote!(#enum_name::#variant(#binding) => #call)
+    });
+
+    Some(quote! {
+        #sig {
+            match self {
+                #(#arms),*
+            }
+        }
+    })
+}
+
+/// Introduces a fresh generic parameter standing in for `assoc`'s projection,
+/// constraining it with `assoc`'s own bounds, and returns the parameter's
+/// identifier together with the `type Assoc = __Assoc;` item for the impl body.
+fn either_assoc_type(
+    assoc: &TraitItemType,
+    extended_generics: &mut Generics,
+) -> (Ident, proc_macro2::TokenStream) {
+    let assoc_name = &assoc.ident;
+    let fresh = format_ident!("__{}", assoc_name);
+    let bounds = &assoc.bounds;
+    if bounds.is_empty() {
+        extended_generics.params.push(parse_quote!(#fresh));
+    } else {
+        extended_generics.params.push(parse_quote!(#fresh: #bounds));
+    }
+    let impl_item = quote! { type #assoc_name = #fresh; };
+    (fresh, impl_item)
+}
+
+/// Builds the bound `#name<...> + #supertraits` required of `L` and `R`,
+/// threading the trait's own generic arguments together with
+/// `Assoc = __Assoc` bindings for each associated type, and requiring
+/// every supertrait of the trait as well (so that `Either<L, R>` itself
+/// satisfies them).
+fn trait_bound(
+    name: &Ident,
+    generics: &Generics,
+    assoc_bindings: &[(Ident, Ident)],
+    supertraits: &Punctuated<TypeParamBound, Token![+]>,
+) -> proc_macro2::TokenStream {
+    let ty_args = generics.params.iter().map(|param| match param {
+        GenericParam::Type(param) => {
+            let ident = &param.ident;
+            quote!(#ident)
+        }
+        GenericParam::Lifetime(param) => {
+            let lifetime = &param.lifetime;
+            quote!(#lifetime)
+        }
+        GenericParam::Const(param) => {
+            let ident = &param.ident;
+            quote!(#ident)
+        }
+    });
+    let assoc_args = assoc_bindings
+        .iter()
+        .map(|(assoc_name, fresh)| quote!(#assoc_name = #fresh));
+    let args: Vec<_> = ty_args.chain(assoc_args).collect();
+    let bound = if args.is_empty() {
+        quote!(#name)
+    } else {
+        quote!(#name<#(#args),*>)
+    };
+    if supertraits.is_empty() {
+        bound
     } else {
-        panic!("The first parameter of a trait method must be `self`, `&self` or `&mut self`.")
+        quote!(#bound + #supertraits)
     }
 }
 
-fn impl_item(name: &Ident, generics: &Generics) -> proc_macro2::TokenStream {
+/// Validates that every variant of `enum_item` is a single-field tuple
+/// variant, and builds the `impl ... for #enum_name #ty_generics` header
+/// dispatching trait `name` across them. Mirrors [`impl_item`], but for an
+/// arbitrary user enum instead of the fixed two-armed `Either`: the impl's
+/// generics are the enum's own generic parameters plus the trait's (so a
+/// generic trait like `trait Holder<T>` still has `T` in scope), and each
+/// variant's field type is required of `#name` via a where-clause
+/// predicate.
+fn enum_impl_item(
+    enum_item: &ItemEnum,
+    name: &Ident,
+    generics: &Generics,
+    items: &[TraitItem],
+    supertraits: &Punctuated<TypeParamBound, Token![+]>,
+    errors: &mut Vec<Error>,
+) -> (
+    proc_macro2::TokenStream,
+    Vec<proc_macro2::TokenStream>,
+    Vec<Ident>,
+) {
+    let enum_name = &enum_item.ident;
+    let (_impl_generics, enum_ty_generics, _where_clause) = enum_item.generics.split_for_impl();
+    let (_impl_generics, trait_ty_generics, _where_clause) = generics.split_for_impl();
+
+    let mut extended_generics = enum_item.generics.clone();
+    extended_generics
+        .params
+        .extend(generics.params.iter().cloned());
+    if let Some(where_clause) = &generics.where_clause {
+        extended_generics
+            .make_where_clause()
+            .predicates
+            .extend(where_clause.predicates.iter().cloned());
+    }
+
+    let mut variants = Vec::new();
+    let mut field_types = Vec::new();
+    for variant in &enum_item.variants {
+        match &variant.fields {
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                variants.push(variant.ident.clone());
+                field_types.push(fields.unnamed.first().unwrap().ty.clone());
+            }
+            _ => errors.push(Error::new(
+                variant.span(),
+                "each variant of the enum must be a single-field tuple variant",
+            )),
+        }
+    }
+
+    let mut assoc_bindings = Vec::new();
+    let mut assoc_impl_items = Vec::new();
+    for item in items {
+        if let TraitItem::Type(assoc) = item {
+            let (fresh, impl_item) = either_assoc_type(assoc, &mut extended_generics);
+            assoc_bindings.push((assoc.ident.clone(), fresh));
+            assoc_impl_items.push(impl_item);
+        }
+    }
+
+    // A field type need not be one of the enum's generic parameters (it may
+    // be a concrete or compound type), so it cannot always carry the bound as
+    // a generic parameter. So every field type is constrained here through a
+    // where-clause predicate, even when it is itself one of the enum's generic
+    // parameters.
+    let bound = trait_bound(name, generics, &assoc_bindings, supertraits);
+    for field_ty in &field_types {
+        extended_generics
+            .make_where_clause()
+            .predicates
+            .push(parse_quote!(#field_ty: #bound));
+    }
+
+    let (impl_generics, _ty_generics, where_clause) = extended_generics.split_for_impl();
+    let header = quote! {
+        impl #impl_generics #name #trait_ty_generics for #enum_name #enum_ty_generics #where_clause
+    };
+
+    (header, assoc_impl_items, variants)
+}
+
+fn impl_item(
+    name: &Ident,
+    generics: &Generics,
+    items: &[TraitItem],
+    supertraits: &Punctuated<TypeParamBound, Token![+]>,
+    errors: &mut Vec<Error>,
+) -> (proc_macro2::TokenStream, Vec<proc_macro2::TokenStream>) {
     let (_impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
     let mut extended_generics = generics.clone();
 
-    assert!(
-        extended_generics.type_params().all(|param| {
-            let name = param.ident.to_string();
-            name != "L" && name != "R"
-        }),
-        "Generic type parameters must not be `L` or `R`."
-    );
+    for param in extended_generics.type_params() {
+        let param_name = param.ident.to_string();
+        if param_name == "L" || param_name == "R" {
+            errors.push(Error::new(
+                param.ident.span(),
+                "generic type parameters must not be `L` or `R`",
+            ));
+        }
+    }
 
-    extended_generics
-        .params
-        .push(parse_quote!(L: #name #ty_generics));
-    extended_generics
-        .params
-        .push(parse_quote!(R: #name #ty_generics));
+    let mut assoc_bindings = Vec::new();
+    let mut assoc_impl_items = Vec::new();
+    for item in items {
+        if let TraitItem::Type(assoc) = item {
+            let (fresh, impl_item) = either_assoc_type(assoc, &mut extended_generics);
+            assoc_bindings.push((assoc.ident.clone(), fresh));
+            assoc_impl_items.push(impl_item);
+        }
+    }
 
-    quote! {
+    let bound = trait_bound(name, generics, &assoc_bindings, supertraits);
+    extended_generics.params.push(parse_quote!(L: #bound));
+    extended_generics.params.push(parse_quote!(R: #bound));
+
+    let header = quote! {
         impl #extended_generics #name #ty_generics for Either<L, R> #where_clause
-    }
+    };
+
+    (header, assoc_impl_items)
 }
 
 #[proc_macro_attribute]
 pub fn either_trait(_args: TokenStream, input: TokenStream) -> TokenStream {
-    let input = parse_macro_input!(input as ItemTrait);
+    let mut input = parse_macro_input!(input as ItemTrait);
 
-    let name = &input.ident;
-    let items = &input.items;
+    let name = input.ident.clone();
+    let mut errors = Vec::new();
 
-    let impl_item = impl_item(&name, &input.generics);
+    let (impl_header, assoc_impl_items) = impl_item(
+        &name,
+        &input.generics,
+        &input.items,
+        &input.supertraits,
+        &mut errors,
+    );
 
-    let impl_methods = items.iter().map(|item| match item {
-        TraitItem::Method(method) => either_method(method),
-        _ => panic!("The trait must be without associated constants or associated types."),
-    });
+    let impl_methods: Vec<_> = input
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            TraitItem::Method(method) if method.attrs.iter().any(is_skip_attr) => {
+                if method.default.is_none() {
+                    errors.push(Error::new(
+                        method.sig.span(),
+                        "a method with `#[either(skip)]` must provide a default implementation",
+                    ));
+                }
+                None
+            }
+            TraitItem::Method(method) => either_method(method, &mut errors),
+            TraitItem::Type(_) => None,
+            other => {
+                errors.push(Error::new(
+                    other.span(),
+                    "the trait must be without associated constants",
+                ));
+                None
+            }
+        })
+        .collect();
+
+    for item in input.items.iter_mut() {
+        if let TraitItem::Method(method) = item {
+            method.attrs.retain(|attr| !is_skip_attr(attr));
+        }
+    }
+
+    if let Some(error) = combine_errors(errors) {
+        let compile_error = error.to_compile_error();
+        return TokenStream::from(quote! {
+            #input
+
+            #compile_error
+        });
+    }
+
+    let expand = quote! {
+        #input
+
+        #impl_header
+        {
+            #(#assoc_impl_items)*
+            #(#impl_methods)*
+        }
+    };
+
+    TokenStream::from(expand)
+}
+
+/// Generalizes `either_trait` to an arbitrary enum of single-field tuple
+/// variants, e.g. a user-defined `Either3`/`Either4`. Since an attribute
+/// macro only sees the item it decorates, the target enum's shape is
+/// restated as the macro's argument: `#[either_enum(enum Either3<A, B, C> {
+/// First(A), Second(B), Third(C) })]`. The enum itself is left untouched
+/// and must already be declared elsewhere with a matching shape.
+#[proc_macro_attribute]
+pub fn either_enum(args: TokenStream, input: TokenStream) -> TokenStream {
+    let enum_item = parse_macro_input!(args as ItemEnum);
+    let mut input = parse_macro_input!(input as ItemTrait);
+
+    let name = input.ident.clone();
+    let mut errors = Vec::new();
+
+    let (impl_header, assoc_impl_items, variants) = enum_impl_item(
+        &enum_item,
+        &name,
+        &input.generics,
+        &input.items,
+        &input.supertraits,
+        &mut errors,
+    );
+
+    let enum_name = &enum_item.ident;
+    let impl_methods: Vec<_> = input
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            TraitItem::Method(method) if method.attrs.iter().any(is_skip_attr) => {
+                if method.default.is_none() {
+                    errors.push(Error::new(
+                        method.sig.span(),
+                        "a method with `#[either(skip)]` must provide a default implementation",
+                    ));
+                }
+                None
+            }
+            TraitItem::Method(method) => {
+                either_enum_method(method, enum_name, &variants, &mut errors)
+            }
+            TraitItem::Type(_) => None,
+            other => {
+                errors.push(Error::new(
+                    other.span(),
+                    "the trait must be without associated constants",
+                ));
+                None
+            }
+        })
+        .collect();
+
+    for item in input.items.iter_mut() {
+        if let TraitItem::Method(method) = item {
+            method.attrs.retain(|attr| !is_skip_attr(attr));
+        }
+    }
+
+    if let Some(error) = combine_errors(errors) {
+        let compile_error = error.to_compile_error();
+        return TokenStream::from(quote! {
+            #input
+
+            #compile_error
+        });
+    }
 
     let expand = quote! {
         #input
 
-        #impl_item
+        #impl_header
         {
+            #(#assoc_impl_items)*
             #(#impl_methods)*
         }
     };
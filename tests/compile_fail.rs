@@ -0,0 +1,8 @@
+//! Compile-fail coverage for the diagnostics emitted by `either_trait` and
+//! `either_enum`: every case in `tests/ui` is expected to fail to compile
+//! with the spanned `syn::Error`s the macros accumulate, not with a panic.
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
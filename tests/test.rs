@@ -1,5 +1,5 @@
 use either::Either;
-use either_trait_macro::either_trait;
+use either_trait_macro::{either_enum, either_trait};
 
 #[either_trait]
 /// An example trait.
@@ -16,8 +16,10 @@ trait Example {
         F: Fn(T) -> T;
 }
 
+#[derive(Clone)]
 struct A;
 
+#[derive(Clone)]
 struct B(i32);
 
 impl Example for A {
@@ -78,3 +80,231 @@ fn test2() {
     either.bar((3, 4));
     assert_eq!(either.foo(0), 9);
 }
+
+#[either_trait]
+/// A trait with an associated type.
+trait Counter {
+    /// The kind of item being counted.
+    type Item;
+
+    /// Counts the items.
+    fn count(&self, items: &[Self::Item]) -> usize;
+}
+
+impl Counter for A {
+    type Item = i32;
+
+    fn count(&self, items: &[i32]) -> usize {
+        items.len()
+    }
+}
+
+impl Counter for B {
+    type Item = i32;
+
+    fn count(&self, items: &[i32]) -> usize {
+        items.iter().filter(|&&x| x == self.0).count()
+    }
+}
+
+#[test]
+fn test_assoc_type() {
+    let left: Either<A, B> = Either::Left(A);
+    assert_eq!(left.count(&[1, 2, 3]), 3);
+
+    let right: Either<A, B> = Either::Right(B(2));
+    assert_eq!(right.count(&[1, 2, 2, 3]), 2);
+}
+
+#[either_trait]
+/// A trait with a method returning `Self`.
+trait Double {
+    /// Doubles the value, returning a new instance.
+    fn double(&self) -> Self;
+}
+
+impl Double for A {
+    fn double(&self) -> A {
+        A
+    }
+}
+
+impl Double for B {
+    fn double(&self) -> B {
+        B(self.0 * 2)
+    }
+}
+
+#[test]
+fn test_self_return() {
+    let left: Either<A, B> = Either::Left(A);
+    assert!(matches!(left.double(), Either::Left(A)));
+
+    let right: Either<A, B> = Either::Right(B(3));
+    match right.double() {
+        Either::Right(B(n)) => assert_eq!(n, 6),
+        _ => panic!("expected Either::Right"),
+    }
+}
+
+#[derive(Clone)]
+enum Either3<X, Y, Z> {
+    First(X),
+    Second(Y),
+    Third(Z),
+}
+
+#[derive(Clone)]
+struct C(i32);
+
+#[either_enum(enum Either3<X, Y, Z> { First(X), Second(Y), Third(Z) })]
+/// A trait dispatched across a three-variant enum.
+trait Triple {
+    /// Multiplies by some factor.
+    fn triple(&self, x: i32) -> i32;
+}
+
+impl Triple for A {
+    fn triple(&self, x: i32) -> i32 {
+        x
+    }
+}
+
+impl Triple for B {
+    fn triple(&self, x: i32) -> i32 {
+        self.0 + x
+    }
+}
+
+impl Triple for C {
+    fn triple(&self, x: i32) -> i32 {
+        self.0 * x
+    }
+}
+
+#[test]
+fn test_either_enum() {
+    let first: Either3<A, B, C> = Either3::First(A);
+    assert_eq!(first.triple(2), 2);
+
+    let second: Either3<A, B, C> = Either3::Second(B(3));
+    assert_eq!(second.triple(2), 5);
+
+    let third: Either3<A, B, C> = Either3::Third(C(4));
+    assert_eq!(third.triple(2), 8);
+}
+
+#[either_enum(enum Either3<X, Y, Z> { First(X), Second(Y), Third(Z) })]
+/// A generic trait dispatched across a three-variant enum: the impl must
+/// keep `T` in scope alongside the enum's own generic parameters.
+trait Holder<T> {
+    /// Returns a value of the held type.
+    fn get(&self) -> T;
+}
+
+impl Holder<i32> for A {
+    fn get(&self) -> i32 {
+        0
+    }
+}
+
+impl Holder<i32> for B {
+    fn get(&self) -> i32 {
+        self.0
+    }
+}
+
+impl Holder<i32> for C {
+    fn get(&self) -> i32 {
+        self.0 * 2
+    }
+}
+
+#[test]
+fn test_either_enum_generic_trait() {
+    let first: Either3<A, B, C> = Either3::First(A);
+    assert_eq!(Holder::<i32>::get(&first), 0);
+
+    let third: Either3<A, B, C> = Either3::Third(C(5));
+    assert_eq!(Holder::<i32>::get(&third), 10);
+}
+
+#[either_trait]
+/// A trait with a supertrait and a default method.
+trait Named: Clone {
+    /// The name of the value.
+    fn name(&self) -> String;
+
+    #[either(skip)]
+    /// A greeting built from the name.
+    fn greet(&self) -> String {
+        format!("Hello, {}!", self.name())
+    }
+}
+
+#[derive(Clone)]
+struct Dog;
+
+#[derive(Clone)]
+struct Cat;
+
+impl Named for Dog {
+    fn name(&self) -> String {
+        "Dog".to_string()
+    }
+}
+
+impl Named for Cat {
+    fn name(&self) -> String {
+        "Cat".to_string()
+    }
+}
+
+#[test]
+fn test_supertrait_and_skip() {
+    let left: Either<Dog, Cat> = Either::Left(Dog);
+    assert_eq!(left.name(), "Dog");
+    assert_eq!(left.greet(), "Hello, Dog!");
+    let left_clone = left.clone();
+    assert_eq!(left_clone.name(), "Dog");
+
+    let right: Either<Dog, Cat> = Either::Right(Cat);
+    assert_eq!(right.greet(), "Hello, Cat!");
+}
+
+#[either_enum(enum Either3<X, Y, Z> { First(X), Second(Y), Third(Z) })]
+/// A trait with a supertrait dispatched across a three-variant enum, whose
+/// variant fields are the enum's own (bare) generic parameters.
+trait Combo: Clone {
+    /// Gets an integer.
+    fn combo_value(&self) -> i32;
+}
+
+impl Combo for A {
+    fn combo_value(&self) -> i32 {
+        1
+    }
+}
+
+impl Combo for B {
+    fn combo_value(&self) -> i32 {
+        self.0
+    }
+}
+
+impl Combo for C {
+    fn combo_value(&self) -> i32 {
+        self.0 * 10
+    }
+}
+
+#[test]
+fn test_either_enum_with_supertrait() {
+    let first: Either3<A, B, C> = Either3::First(A);
+    assert_eq!(first.combo_value(), 1);
+    let first_clone = first.clone();
+    assert_eq!(first_clone.combo_value(), 1);
+
+    let third: Either3<A, B, C> = Either3::Third(C(3));
+    assert_eq!(third.combo_value(), 30);
+}
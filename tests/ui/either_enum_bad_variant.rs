@@ -0,0 +1,13 @@
+use either_trait_macro::either_enum;
+
+enum NotSingleField<X, Y> {
+    First(X),
+    Second(Y, Y),
+}
+
+#[either_enum(enum NotSingleField<X, Y> { First(X), Second(Y, Y) })]
+trait Triple {
+    fn triple(&self, x: i32) -> i32;
+}
+
+fn main() {}
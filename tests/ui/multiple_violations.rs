@@ -0,0 +1,12 @@
+use either_trait_macro::either_trait;
+
+#[either_trait]
+trait Bad {
+    const N: i32;
+
+    fn no_receiver(x: i32) -> i32;
+
+    fn self_param(&self, other: Self) -> i32;
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use either_trait_macro::either_trait;
+
+#[either_trait]
+trait Combine {
+    fn combine(&self, other: &Self) -> i32;
+}
+
+fn main() {}